@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+/// A single cached static file entry: its raw bytes, resolved content-type
+/// and the disk modification time it was read at.
+#[derive(Clone)]
+pub struct CachedFile {
+    pub contents: Vec<u8>,
+    pub content_type: String,
+    pub mtime_secs: u64,
+}
+
+/// A byte-capped, in-memory LRU cache for static file contents, keyed by
+/// request path. Entries are evicted oldest-first once `max_bytes` is
+/// exceeded.
+pub struct LruCache {
+    entries: HashMap<String, CachedFile>,
+    // Most-recently-used key is at the back.
+    order: VecDeque<String>,
+    max_bytes: usize,
+    used_bytes: usize,
+}
+
+impl LruCache {
+    pub fn new(max_bytes: usize) -> LruCache {
+        LruCache {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            max_bytes,
+            used_bytes: 0,
+        }
+    }
+
+    /// Looks up an entry, marking it as most-recently-used on a hit.
+    pub fn get(&mut self, key: &str) -> Option<CachedFile> {
+        let entry = self.entries.get(key).cloned();
+        if entry.is_some() {
+            self.touch(key);
+        }
+        entry
+    }
+
+    /// Removes an entry from the cache, e.g. once it's detected to be stale.
+    pub fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.used_bytes -= entry.contents.len();
+            self.order.retain(|existing| existing != key);
+        }
+    }
+
+    /// Inserts or replaces an entry, evicting the least-recently-used
+    /// entries until the cache fits within `max_bytes` again.
+    pub fn put(&mut self, key: String, entry: CachedFile) {
+        self.remove(&key);
+        let size = entry.contents.len();
+        if size > self.max_bytes {
+            return;
+        }
+        while self.used_bytes + size > self.max_bytes {
+            match self.order.pop_front() {
+                Some(oldest) => self.remove(&oldest),
+                None => break,
+            }
+        }
+        self.used_bytes += size;
+        self.order.push_back(key.clone());
+        self.entries.insert(key, entry);
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.order.retain(|existing| existing != key);
+        self.order.push_back(key.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(contents: &[u8]) -> CachedFile {
+        CachedFile {
+            contents: contents.to_vec(),
+            content_type: "text/plain".to_string(),
+            mtime_secs: 0,
+        }
+    }
+
+    #[test]
+    fn returns_a_hit_after_put() {
+        let mut cache = LruCache::new(100);
+        cache.put("/a".to_string(), file(b"hello"));
+        assert!(cache.get("/a").is_some());
+    }
+
+    #[test]
+    fn returns_none_for_a_miss() {
+        let mut cache = LruCache::new(100);
+        assert!(cache.get("/missing").is_none());
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry_once_over_capacity() {
+        let mut cache = LruCache::new(10);
+        cache.put("/a".to_string(), file(b"aaaaa"));
+        cache.put("/b".to_string(), file(b"bbbbb"));
+        // Pushes total usage to 15 bytes, over the 10 byte cap, so the
+        // least-recently-used entry ("/a") should be evicted to make room.
+        cache.put("/c".to_string(), file(b"ccccc"));
+
+        assert!(cache.get("/a").is_none());
+        assert!(cache.get("/b").is_some());
+        assert!(cache.get("/c").is_some());
+    }
+
+    #[test]
+    fn get_marks_an_entry_as_recently_used_so_it_survives_eviction() {
+        let mut cache = LruCache::new(10);
+        cache.put("/a".to_string(), file(b"aaaaa"));
+        cache.put("/b".to_string(), file(b"bbbbb"));
+        // Touch "/a" so "/b" becomes the least-recently-used entry instead.
+        cache.get("/a");
+        cache.put("/c".to_string(), file(b"ccccc"));
+
+        assert!(cache.get("/a").is_some());
+        assert!(cache.get("/b").is_none());
+    }
+
+    #[test]
+    fn an_entry_larger_than_the_cache_is_never_stored() {
+        let mut cache = LruCache::new(5);
+        cache.put("/a".to_string(), file(b"way too big"));
+        assert!(cache.get("/a").is_none());
+    }
+
+    #[test]
+    fn remove_frees_its_bytes_for_later_entries() {
+        let mut cache = LruCache::new(10);
+        cache.put("/a".to_string(), file(b"aaaaa"));
+        cache.remove("/a");
+        cache.put("/b".to_string(), file(b"bbbbb"));
+        cache.put("/c".to_string(), file(b"ccccc"));
+
+        assert!(cache.get("/a").is_none());
+        assert!(cache.get("/b").is_some());
+        assert!(cache.get("/c").is_some());
+    }
+}
@@ -0,0 +1,128 @@
+/// An inclusive byte range to serve from a file, as resolved from a `Range`
+/// request header.
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+pub struct FileParser;
+
+impl FileParser {
+    /// Map a file extension to its MIME content-type, defaulting to
+    /// `application/octet-stream` for unknown extensions.
+    pub fn get_type(file_ending: &str) -> &str {
+        match file_ending {
+            "html" | "htm" => "text/html",
+            "css" => "text/css",
+            "js" => "application/javascript",
+            "json" => "application/json",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            "ico" => "image/x-icon",
+            "txt" => "text/plain",
+            "pdf" => "application/pdf",
+            "mp4" => "video/mp4",
+            "webm" => "video/webm",
+            "mp3" => "audio/mpeg",
+            "wav" => "audio/wav",
+            _ => "application/octet-stream",
+        }
+    }
+
+    /// Parse a `Range: bytes=...` header value against `file_len`, returning
+    /// the inclusive start/end byte offsets that should be served.
+    ///
+    /// Supports a single `bytes=start-end`, an open-ended `bytes=start-`
+    /// (read to EOF), and a suffix `bytes=-N` (last N bytes) range. Returns
+    /// `None` if the header is malformed, or the range cannot be satisfied
+    /// by a file of `file_len` bytes.
+    pub fn parse_range(range_header: &str, file_len: u64) -> Option<ByteRange> {
+        let spec = range_header.trim().strip_prefix("bytes=")?;
+        // Only a single range is supported, ignore any further ranges.
+        let spec = spec.split(',').next()?.trim();
+        let (start_str, end_str) = spec.split_once('-')?;
+
+        if start_str.is_empty() {
+            // Suffix range: bytes=-N -> last N bytes of the file.
+            let suffix_len: u64 = end_str.parse().ok()?;
+            if suffix_len == 0 || file_len == 0 {
+                return None;
+            }
+            let suffix_len = suffix_len.min(file_len);
+            return Some(ByteRange {
+                start: file_len - suffix_len,
+                end: file_len - 1,
+            });
+        }
+
+        let start: u64 = start_str.parse().ok()?;
+        if file_len == 0 || start >= file_len {
+            return None;
+        }
+        let end = if end_str.is_empty() {
+            file_len - 1
+        } else {
+            end_str.parse::<u64>().ok()?.min(file_len - 1)
+        };
+        if end < start {
+            return None;
+        }
+        Some(ByteRange { start, end })
+    }
+
+    /// Computes a weak `ETag` for a file from its length and modification
+    /// time, e.g. `W/"1024-1699000000"`.
+    pub fn compute_etag(len: u64, mtime_secs: u64) -> String {
+        format!("W/\"{}-{}\"", len, mtime_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_bounded_range() {
+        let range = FileParser::parse_range("bytes=0-99", 1000).unwrap();
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, 99);
+    }
+
+    #[test]
+    fn parses_an_open_ended_range() {
+        let range = FileParser::parse_range("bytes=900-", 1000).unwrap();
+        assert_eq!(range.start, 900);
+        assert_eq!(range.end, 999);
+    }
+
+    #[test]
+    fn parses_a_suffix_range() {
+        let range = FileParser::parse_range("bytes=-100", 1000).unwrap();
+        assert_eq!(range.start, 900);
+        assert_eq!(range.end, 999);
+    }
+
+    #[test]
+    fn clamps_a_suffix_range_longer_than_the_file() {
+        let range = FileParser::parse_range("bytes=-9999", 1000).unwrap();
+        assert_eq!(range.start, 0);
+        assert_eq!(range.end, 999);
+    }
+
+    #[test]
+    fn rejects_a_range_starting_past_the_end_of_the_file() {
+        assert!(FileParser::parse_range("bytes=1000-", 1000).is_none());
+    }
+
+    #[test]
+    fn rejects_an_inverted_range() {
+        assert!(FileParser::parse_range("bytes=50-10", 1000).is_none());
+    }
+
+    #[test]
+    fn rejects_a_malformed_header() {
+        assert!(FileParser::parse_range("not-a-range", 1000).is_none());
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::request::Request;
+use crate::response::Response;
+
+/// Largest `Content-Length` a request body is allowed to declare, so a
+/// client can't force a huge up-front allocation (or, on a keep-alive
+/// connection, repeat that for every request) just by sending a large
+/// header value.
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Outcome of trying to read one request off of a connection.
+pub enum ParseOutcome {
+    /// A full request was read.
+    Request(Request),
+    /// The connection ended or could not be read as a request: the client
+    /// closed it (before or partway through a request; closing before a new
+    /// request is the expected way a keep-alive connection ends), the
+    /// request line was malformed, or a read failed with a non-timeout error.
+    Closed,
+    /// No complete request arrived within the configured read timeout.
+    Timeout,
+    /// The request declared a `Content-Length` larger than `MAX_BODY_BYTES`.
+    BodyTooLarge,
+}
+
+/// Reads a single HTTP request off of `stream`, parsing the request line,
+/// headers and (if present) a body sized by `Content-Length`.
+///
+/// `stream`'s read timeout is expected to already be set to however long a
+/// connection may sit idle before this request starts arriving (e.g. the
+/// keep-alive wait). Once the request line arrives, the timeout is switched
+/// to `request_timeout_secs` so a slow client can't stall mid-headers/body
+/// under the (possibly much longer) idle timeout instead.
+pub fn parse_request(stream: &mut TcpStream, request_timeout_secs: u64) -> ParseOutcome {
+    let mut reader = BufReader::new(match stream.try_clone() {
+        Ok(cloned) => cloned,
+        Err(_) => return ParseOutcome::Closed,
+    });
+
+    let mut request_line = String::new();
+    match reader.read_line(&mut request_line) {
+        Ok(0) => return ParseOutcome::Closed,
+        Ok(_) => {}
+        Err(error) if is_timeout(&error) => return ParseOutcome::Timeout,
+        Err(_) => return ParseOutcome::Closed,
+    }
+    let _ = reader
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_secs(request_timeout_secs)));
+
+    let mut parts = request_line.split_whitespace();
+    let (method, url, version) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(url), version) => (
+            method.to_string(),
+            url.to_string(),
+            version.unwrap_or("HTTP/1.1").to_string(),
+        ),
+        _ => return ParseOutcome::Closed,
+    };
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) => return ParseOutcome::Closed,
+            Ok(_) => {}
+            Err(error) if is_timeout(&error) => return ParseOutcome::Timeout,
+            Err(_) => return ParseOutcome::Closed,
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(idx) = line.find(':') {
+            let key = line[..idx].trim().to_lowercase();
+            let value = line[idx + 1..].trim().to_string();
+            headers.insert(key, value);
+        }
+    }
+
+    let content_length: usize = headers
+        .get("content-length")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    if content_length > MAX_BODY_BYTES {
+        return ParseOutcome::BodyTooLarge;
+    }
+    let mut body = vec![0; content_length];
+    if content_length > 0 {
+        if let Err(error) = reader.read_exact(&mut body) {
+            return if is_timeout(&error) {
+                ParseOutcome::Timeout
+            } else {
+                ParseOutcome::Closed
+            };
+        }
+    }
+
+    let mut request = Request::new(method, url, version, headers, body);
+    request.params = parse_query_params(&request.url);
+    ParseOutcome::Request(request)
+}
+
+/// Whether an IO error represents a read timeout rather than a closed/reset
+/// connection.
+fn is_timeout(error: &std::io::Error) -> bool {
+    matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+/// Parses the query string (if any) off of a request URL into a parameter
+/// map, e.g. `/user/?name=Alice&age=30` -> `{"name": "Alice", "age": "30"}`.
+fn parse_query_params(url: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    if let Some((_, query)) = url.split_once('?') {
+        for pair in query.split('&') {
+            if let Some((key, value)) = pair.split_once('=') {
+                params.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    params
+}
+
+/// Serializes `res` as an HTTP response and writes it back to the client.
+pub fn write_response(stream: &mut TcpStream, res: &Response) {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        res.status_code,
+        status_text(res.status_code)
+    );
+    for (key, value) in res.headers.iter() {
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    head.push_str(&format!("Content-Length: {}\r\n", res.body.len()));
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(&res.body);
+    let _ = stream.flush();
+}
+
+/// Maps a status code to its standard reason phrase.
+fn status_text(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        416 => "Range Not Satisfiable",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Binds a loopback listener, connects a client to it, and returns the
+    /// accepted server-side stream together with the client-side stream
+    /// for the test to write to.
+    fn accepted_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (server, client)
+    }
+
+    #[test]
+    fn closes_on_eof_before_any_request_line() {
+        let (mut server, client) = accepted_pair();
+        drop(client);
+        assert!(matches!(
+            parse_request(&mut server, 1),
+            ParseOutcome::Closed
+        ));
+    }
+
+    #[test]
+    fn closes_on_eof_mid_headers() {
+        let (mut server, mut client) = accepted_pair();
+        client.write_all(b"GET / HTTP/1.1\r\nHost: example.com\r\n").unwrap();
+        drop(client);
+        assert!(matches!(
+            parse_request(&mut server, 1),
+            ParseOutcome::Closed
+        ));
+    }
+
+    #[test]
+    fn times_out_when_no_request_line_arrives() {
+        let (mut server, client) = accepted_pair();
+        server
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        assert!(matches!(
+            parse_request(&mut server, 1),
+            ParseOutcome::Timeout
+        ));
+        drop(client);
+    }
+
+    #[test]
+    fn rejects_a_body_larger_than_the_cap() {
+        let (mut server, mut client) = accepted_pair();
+        let oversized = MAX_BODY_BYTES + 1;
+        client
+            .write_all(format!("POST /upload HTTP/1.1\r\nContent-Length: {}\r\n\r\n", oversized).as_bytes())
+            .unwrap();
+        assert!(matches!(
+            parse_request(&mut server, 1),
+            ParseOutcome::BodyTooLarge
+        ));
+    }
+
+    #[test]
+    fn parses_a_well_formed_request_with_a_body() {
+        let (mut server, mut client) = accepted_pair();
+        client
+            .write_all(b"POST /echo HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nhello")
+            .unwrap();
+        match parse_request(&mut server, 1) {
+            ParseOutcome::Request(request) => {
+                assert_eq!(request.method, "POST");
+                assert_eq!(request.url, "/echo");
+                assert_eq!(request.body, b"hello");
+            }
+            _ => panic!("expected a parsed request"),
+        }
+    }
+}
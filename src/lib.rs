@@ -1,27 +1,60 @@
 use std::collections::HashMap;
 use std::env;
 use std::fs;
+use std::io;
 use std::io::prelude::*;
+use std::io::SeekFrom;
 use std::net::TcpListener;
-use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
 
+mod cache;
 mod file_parser;
 mod http_parser;
 pub mod request;
 pub mod response;
+mod router;
 mod threadpool;
+use cache::CachedFile;
+use cache::LruCache;
 use file_parser::FileParser;
 use request::Request;
 use response::Response;
 use threadpool::ThreadPool;
 
+/// Default cap on how many bytes of static file content are kept cached in
+/// memory, used until `cache_limit` is called.
+const DEFAULT_CACHE_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Default number of seconds each socket read may wait while a request is
+/// arriving before the server responds with `408 Request Timeout`.
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Default number of seconds an idle keep-alive connection is held open
+/// waiting for the next request before the server closes it.
+const DEFAULT_KEEP_ALIVE_SECS: u64 = 5;
+
+/// A route handler: takes the request and a blank response, returns the
+/// response to write back to the client.
+pub type Handler = fn(Request, Response) -> Response;
+
 pub struct RustHTTPServer {
     /// The amount of worker threads used to handle requests
     amount_of_threads: usize,
-    // Contains all the routes for http resources on the server
-    routes: HashMap<String, fn(Request, Response) -> Response>,
+    // Contains all the routes for http resources on the server, keyed by
+    // path and then by HTTP method.
+    routes: HashMap<String, HashMap<String, Handler>>,
     // Contains all the middleware for the servers resources
     middleware: Vec<(String, fn(Request, Response) -> (Request, Response, bool))>,
+    // Shared in-memory cache of static file contents, cloned into the
+    // ThreadPool so route handlers can read/populate it via `req.cache`.
+    cache: Arc<Mutex<LruCache>>,
+    // How long each socket read may wait while a request is arriving before
+    // the connection is answered with 408 and disconnected.
+    request_timeout_secs: u64,
+    // How long an idle keep-alive connection is kept open between requests.
+    keep_alive_secs: u64,
 }
 
 impl RustHTTPServer {
@@ -35,9 +68,34 @@ impl RustHTTPServer {
             amount_of_threads: amount_of_threads,
             routes: HashMap::new(),
             middleware: Vec::new(),
+            cache: Arc::new(Mutex::new(LruCache::new(DEFAULT_CACHE_LIMIT_BYTES))),
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            keep_alive_secs: DEFAULT_KEEP_ALIVE_SECS,
         };
     }
 
+    /// Set the maximum number of bytes of static file content kept cached in
+    /// memory. Replaces the current cache, so call this before serving
+    /// requests.
+    pub fn cache_limit(&mut self, bytes: usize) {
+        self.cache = Arc::new(Mutex::new(LruCache::new(bytes)));
+    }
+
+    /// Configure how long, in seconds, each socket read may wait while a
+    /// request is arriving before the server responds with `408 Request
+    /// Timeout` and disconnects it. The limit applies per read, not to the
+    /// request as a whole: a client that keeps sending some bytes within
+    /// every interval is not cut off.
+    pub fn request_timeout(&mut self, secs: u64) {
+        self.request_timeout_secs = secs;
+    }
+
+    /// Configure how long, in seconds, an idle keep-alive connection is held
+    /// open waiting for the next request before the server closes it.
+    pub fn keep_alive(&mut self, secs: u64) {
+        self.keep_alive_secs = secs;
+    }
+
     /// Add middleware for specified resources.
     ///
     /// The middleware function takes inn a function that returns a modified response and request, aswell as a boolean is true if the request should be forwarded or false if you wish the server to write the current response.
@@ -46,25 +104,66 @@ impl RustHTTPServer {
         path: &str,
         function: fn(Request, Response) -> (Request, Response, bool),
     ) {
-        let mut path_string = String::from(path);
-        // Remove trailing / so that pathing is agnostic towards /example/ or /example
-        match path_string.pop() {
-            Some(last_char) => {
-                if last_char != '/' || path_string.len() == 0 {
-                    path_string.push(last_char)
-                }
-            }
-            None => {
-                path_string.push('/');
-            }
-        };
+        let path_string = RustHTTPServer::normalize_path(path);
         self.middleware.push((path_string, function));
     }
 
-    /// Add a http resource route which takes in the request and a premade respons, then returns a modifed response that is written to the client
-    pub fn route(&mut self, path: &str, function: fn(Request, Response) -> Response) {
+    /// Add a http resource route for a specific HTTP method (e.g. `"GET"`),
+    /// which takes in the request and a premade response, then returns a
+    /// modified response that is written to the client. Prefer the
+    /// `get`/`post`/`put`/... helpers below over calling this directly.
+    pub fn route(&mut self, method: &str, path: &str, function: Handler) {
+        let path_string = RustHTTPServer::normalize_path(path);
+        let method_string = method.to_uppercase();
+        let methods = self.routes.entry(path_string).or_default();
+        if methods.contains_key(&method_string) {
+            println!(
+                "Warning: Route defined twice ({} {}), using latest definition",
+                method_string, path
+            );
+        }
+        methods.insert(method_string, function);
+    }
+
+    /// Add a route that handles `GET` requests to `path`.
+    pub fn get(&mut self, path: &str, function: Handler) {
+        self.route("GET", path, function);
+    }
+
+    /// Add a route that handles `POST` requests to `path`.
+    pub fn post(&mut self, path: &str, function: Handler) {
+        self.route("POST", path, function);
+    }
+
+    /// Add a route that handles `PUT` requests to `path`.
+    pub fn put(&mut self, path: &str, function: Handler) {
+        self.route("PUT", path, function);
+    }
+
+    /// Add a route that handles `DELETE` requests to `path`.
+    pub fn delete(&mut self, path: &str, function: Handler) {
+        self.route("DELETE", path, function);
+    }
+
+    /// Add a route that handles `PATCH` requests to `path`.
+    pub fn patch(&mut self, path: &str, function: Handler) {
+        self.route("PATCH", path, function);
+    }
+
+    /// Add a route that handles `OPTIONS` requests to `path`, overriding the
+    /// automatic `Allow`-header response the server would otherwise send.
+    pub fn options(&mut self, path: &str, function: Handler) {
+        self.route("OPTIONS", path, function);
+    }
+
+    /// Add a route that handles `HEAD` requests to `path`.
+    pub fn head(&mut self, path: &str, function: Handler) {
+        self.route("HEAD", path, function);
+    }
+
+    /// Remove trailing / so that pathing is agnostic towards /example/ or /example
+    fn normalize_path(path: &str) -> String {
         let mut path_string = String::from(path);
-        // Remove trailing / so that pathing is agnostic towards /example/ or /example
         match path_string.pop() {
             Some(last_char) => {
                 if last_char != '/' || path_string.len() == 0 {
@@ -75,97 +174,26 @@ impl RustHTTPServer {
                 path_string.push('/');
             }
         };
-        if self.routes.contains_key(&path_string) {
-            println!(
-                "Warning: Route defined twice ({}), using latest definition",
-                path
-            );
-            self.routes.remove(&path_string);
-        }
-        self.routes.insert(path_string, function);
+        path_string
     }
 
     /// Add a file to routes, it's route is equal to the path where the file lies
     pub fn route_file(&mut self, path: &str) {
-        fn function(req: Request, mut res: Response) -> Response {
-            if req.method == "GET" {
-                let path = req.url;
-                let path_split = path.split('.');
-                let file_ending = match path_split.last() {
-                    Some(file_ending) => file_ending,
-                    None => "",
-                };
-                let file_type = FileParser::get_type(file_ending);
-                // remove first / from path and read metadata then file
-                match fs::metadata(&path[1..]) {
-                    Ok(metadata) => {
-                        let mut contents = vec![0; metadata.len() as usize];
-                        match fs::File::open(&path[1..]) {
-                            Ok(mut file) => {
-                                let result = file.read(&mut contents);
-                                match result {
-                                    Ok(_) => {
-                                        res.status(200);
-                                        res.body_bytes(contents);
-                                        res.header("content-type", file_type);
-                                    }
-                                    Err(error) => {
-                                        println!("{}", error);
-                                        res.status(500);
-                                    }
-                                }
-                            }
-                            Err(error) => {
-                                println!("{}", error);
-                                res.status(500);
-                            }
-                        }
-                    }
-                    Err(error) => {
-                        println!("{}", error);
-                        res.status(500);
-                    }
-                }
-            }
-            return res;
-        };
         // Replace Windows specific backslashes in path with forward slashes
         let result = path.replace("\\", "/");
         let route_path = format!("/{}", result);
-        RustHTTPServer::route(self, &route_path, function);
-    }
-
-    /// Recursive function that adds all the files in the public folder to the server routes
-    fn add_static_files(&mut self, directory: &Path, path: &str) {
-        let dir_iter = fs::read_dir(path).unwrap();
-
-        // Add all files to path hashmap, for each directory in the public folder we run this function recursivly
-        for item in dir_iter {
-            match item {
-                Ok(item_uw) => {
-                    let item_path = item_uw.path().into_os_string().into_string().unwrap();
-                    let item_metadata = item_uw.metadata().unwrap();
-                    if item_metadata.is_dir() {
-                        RustHTTPServer::add_static_files(self, directory, &item_path);
-                    } else {
-                        RustHTTPServer::route_file(self, &item_path);
-                    }
-                }
-                Err(error) => {
-                    println!("{}", error);
-                }
-            };
-        }
+        RustHTTPServer::get(self, &route_path, serve_static_file);
     }
 
-    /// Make all the files in the specified directory publicly avalible
+    /// Make all the files in the specified directory publicly avalible by
+    /// mounting the whole directory under a single `*tail` wildcard route,
+    /// instead of registering one route per file.
     pub fn public(&mut self, dir_name: &str) {
         let path = env::current_dir().unwrap();
         let new_root_dir = path.join(dir_name);
         // Set the specified directory as the root when reading files
         assert!(env::set_current_dir(&new_root_dir).is_ok());
-        let dir = env::current_dir().unwrap();
-        self.add_static_files(dir.as_path(), "");
+        self.get("/*tail", serve_static_file);
     }
 
     /// Bind the server to the specified IP address and listen for inncomming http requests
@@ -182,9 +210,17 @@ impl RustHTTPServer {
         // clone routes and middleware
         let routes_clone = self.routes.clone();
         let middleware_clone = self.middleware.clone();
+        let cache_clone = Arc::clone(&self.cache);
 
         // Create threadpool
-        let pool = ThreadPool::new(self.amount_of_threads, routes_clone, middleware_clone);
+        let pool = ThreadPool::new(
+            self.amount_of_threads,
+            routes_clone,
+            middleware_clone,
+            cache_clone,
+            self.request_timeout_secs,
+            self.keep_alive_secs,
+        );
 
         println!("RustHTTPServer server listening on: http://{}", ip);
         for stream in listener.incoming() {
@@ -198,3 +234,186 @@ impl RustHTTPServer {
         return String::from("Shutting down.");
     }
 }
+
+/// Serves a static file for `req.url`, honouring conditional-GET and Range
+/// headers. Shared by `route_file` (a single explicit path) and `public`
+/// (a whole directory mounted under a `*tail` wildcard route).
+fn serve_static_file(req: Request, mut res: Response) -> Response {
+    let path = req.url.clone();
+    if !is_safe_static_path(&path) {
+        res.status(404);
+        return res;
+    }
+    let file_ending = path.split('.').last().unwrap_or("");
+    let file_type = FileParser::get_type(file_ending);
+    // remove first / from path and read metadata then file
+    match fs::metadata(&path[1..]) {
+        Ok(metadata) => {
+            let file_len = metadata.len();
+            let mtime_secs = mtime_secs(&metadata);
+            let etag = FileParser::compute_etag(file_len, mtime_secs);
+            let last_modified = response::format_http_date(mtime_secs);
+
+            res.header("accept-ranges", "bytes");
+            res.header("etag", &etag);
+            res.header("last-modified", &last_modified);
+
+            if not_modified(&req, &etag, mtime_secs) {
+                res.status(304);
+            } else {
+                match req.header("range") {
+                    Some(range_header) => {
+                        serve_range(&path[1..], range_header, file_len, file_type, &mut res)
+                    }
+                    None => match &req.cache {
+                        Some(cache) => serve_whole_file_cached(
+                            &path,
+                            &path[1..],
+                            file_type,
+                            mtime_secs,
+                            cache,
+                            &mut res,
+                        ),
+                        None => serve_whole_file(&path[1..], file_type, &mut res),
+                    },
+                }
+            }
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            res.status(404);
+        }
+        Err(error) => {
+            println!("{}", error);
+            res.status(500);
+        }
+    }
+    res
+}
+
+/// Rejects any request path containing a `..` segment, so a `public()`
+/// wildcard mount can't be used to read files outside the served directory.
+fn is_safe_static_path(path: &str) -> bool {
+    !path.split('/').any(|segment| segment == "..")
+}
+
+/// Extracts a file's modification time as seconds since the unix epoch,
+/// falling back to `0` if the platform can't report it.
+fn mtime_secs(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Checks the request's `If-None-Match`/`If-Modified-Since` headers against
+/// the current file's validators to decide if a `304 Not Modified` should be
+/// returned instead of re-sending the body.
+fn not_modified(req: &Request, etag: &str, mtime_secs: u64) -> bool {
+    if let Some(if_none_match) = req.header("if-none-match") {
+        return if_none_match.trim() == etag;
+    }
+    if let Some(if_modified_since) = req.header("if-modified-since") {
+        if let Some(since_secs) = response::parse_http_date(if_modified_since) {
+            return mtime_secs <= since_secs;
+        }
+    }
+    false
+}
+
+/// Reads the whole file at `path` and writes it to `res` as a plain `200`.
+fn serve_whole_file(path: &str, file_type: &str, res: &mut Response) {
+    match fs::read(path) {
+        Ok(contents) => {
+            res.status(200);
+            res.header("content-type", file_type);
+            res.body_bytes(contents);
+        }
+        Err(error) => {
+            println!("{}", error);
+            res.status(500);
+        }
+    }
+}
+
+/// Same as `serve_whole_file`, but checks `cache` for a still-fresh copy of
+/// the file before touching disk, and stores what it reads back into the
+/// cache for the next request.
+fn serve_whole_file_cached(
+    cache_key: &str,
+    disk_path: &str,
+    file_type: &str,
+    mtime_secs: u64,
+    cache: &Arc<Mutex<LruCache>>,
+    res: &mut Response,
+) {
+    let cached = cache.lock().unwrap().get(cache_key);
+    if let Some(cached) = cached {
+        if cached.mtime_secs == mtime_secs {
+            res.status(200);
+            res.header("content-type", &cached.content_type);
+            res.body_bytes(cached.contents);
+            return;
+        }
+        // File changed on disk since it was cached, evict and re-read below.
+        cache.lock().unwrap().remove(cache_key);
+    }
+
+    match fs::read(disk_path) {
+        Ok(contents) => {
+            res.status(200);
+            res.header("content-type", file_type);
+            cache.lock().unwrap().put(
+                cache_key.to_string(),
+                CachedFile {
+                    contents: contents.clone(),
+                    content_type: file_type.to_string(),
+                    mtime_secs,
+                },
+            );
+            res.body_bytes(contents);
+        }
+        Err(error) => {
+            println!("{}", error);
+            res.status(500);
+        }
+    }
+}
+
+/// Resolves `range_header` against `file_len` and writes either a `206
+/// Partial Content` response with the requested bytes, or a `416 Range Not
+/// Satisfiable` response if the range cannot be served.
+fn serve_range(path: &str, range_header: &str, file_len: u64, file_type: &str, res: &mut Response) {
+    match FileParser::parse_range(range_header, file_len) {
+        Some(range) => match read_byte_range(path, range.start, range.end) {
+            Ok(contents) => {
+                res.status(206);
+                res.header(
+                    "content-range",
+                    &format!("bytes {}-{}/{}", range.start, range.end, file_len),
+                );
+                res.header("content-type", file_type);
+                res.body_bytes(contents);
+            }
+            Err(error) => {
+                println!("{}", error);
+                res.status(500);
+            }
+        },
+        None => {
+            res.status(416);
+            res.header("content-range", &format!("bytes */{}", file_len));
+        }
+    }
+}
+
+/// Seeks to `start` and reads exactly `end - start + 1` bytes from the file
+/// at `path`, instead of loading the whole file into memory.
+fn read_byte_range(path: &str, start: u64, end: u64) -> io::Result<Vec<u8>> {
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut contents = vec![0; (end - start + 1) as usize];
+    file.read_exact(&mut contents)?;
+    Ok(contents)
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::cache::LruCache;
+
+/// Represents a single incoming HTTP request, parsed from the client socket
+/// and handed to route/middleware handlers.
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    pub version: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+    pub params: HashMap<String, String>,
+    /// Shared static-file cache, attached by the thread pool so that plain
+    /// `fn` route handlers (which cannot capture state) can still reach it.
+    pub cache: Option<Arc<Mutex<LruCache>>>,
+}
+
+impl Request {
+    pub fn new(
+        method: String,
+        url: String,
+        version: String,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    ) -> Request {
+        Request {
+            method,
+            url,
+            version,
+            headers,
+            body,
+            params: HashMap::new(),
+            cache: None,
+        }
+    }
+
+    /// Checks that every key in `keys` is present in `params`, returning the
+    /// first missing key it finds, or `None` if all of them are present.
+    pub fn contains_params(&self, keys: Vec<&str>) -> Option<String> {
+        for key in keys {
+            if !self.params.contains_key(key) {
+                return Some(String::from(key));
+            }
+        }
+        None
+    }
+
+    /// Case-insensitive lookup of a request header.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name_lower = name.to_lowercase();
+        for (key, value) in self.headers.iter() {
+            if key.to_lowercase() == name_lower {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+/// Represents the HTTP response being built up by a route or middleware
+/// handler before it is written back to the client.
+pub struct Response {
+    pub status_code: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Default for Response {
+    fn default() -> Response {
+        Response::new()
+    }
+}
+
+impl Response {
+    pub fn new() -> Response {
+        Response {
+            status_code: 404,
+            headers: HashMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Set the HTTP status code of the response.
+    pub fn status(&mut self, code: u16) {
+        self.status_code = code;
+    }
+
+    /// Set a response header, overwriting any previous value for the same key.
+    pub fn header(&mut self, key: &str, value: &str) {
+        self.headers.insert(String::from(key), String::from(value));
+    }
+
+    /// Set the response body from a string.
+    pub fn body<S: Into<String>>(&mut self, body: S) {
+        self.body = body.into().into_bytes();
+    }
+
+    /// Set the response body from raw bytes, e.g. a file's contents.
+    pub fn body_bytes(&mut self, body: Vec<u8>) {
+        self.body = body;
+    }
+}
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats a unix timestamp (seconds since epoch) as an RFC 7231 HTTP date,
+/// e.g. `Sun, 06 Nov 1994 08:49:37 GMT`, for use in `Last-Modified` headers.
+pub fn format_http_date(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let time_of_day = secs % 86400;
+    let (hour, minute, second) = (time_of_day / 3600, (time_of_day / 60) % 60, time_of_day % 60);
+    // Unix epoch (1970-01-01) was a Thursday.
+    let weekday = DAY_NAMES[((days % 7 + 4 + 7) % 7) as usize];
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        weekday, day, MONTH_NAMES[(month - 1) as usize], year, hour, minute, second
+    )
+}
+
+/// Parses an RFC 7231 HTTP date (as produced by `format_http_date`) back into
+/// a unix timestamp, for comparing against an `If-Modified-Since` header.
+pub fn parse_http_date(value: &str) -> Option<u64> {
+    let mut fields = value.split_whitespace();
+    fields.next()?; // weekday, e.g. "Sun,"
+    let day: i64 = fields.next()?.parse().ok()?;
+    let month_name = fields.next()?;
+    let month = MONTH_NAMES.iter().position(|name| *name == month_name)? as i64 + 1;
+    let year: i64 = fields.next()?.parse().ok()?;
+    let mut time_parts = fields.next()?.split(':');
+    let hour: i64 = time_parts.next()?.parse().ok()?;
+    let minute: i64 = time_parts.next()?.parse().ok()?;
+    let second: i64 = time_parts.next()?.parse().ok()?;
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86400 + hour * 3600 + minute * 60 + second;
+    if secs < 0 {
+        None
+    } else {
+        Some(secs as u64)
+    }
+}
+
+/// Howard Hinnant's civil-from-days algorithm: converts a day count since the
+/// unix epoch into a `(year, month, day)` civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+
+/// Inverse of `civil_from_days`: converts a `(year, month, day)` civil date
+/// into a day count since the unix epoch.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+/// Attempts to match a registered route pattern (e.g. `/user/:id` or
+/// `/public/*tail`) against a request path, returning the captured
+/// `:name`/`*tail` parameters on a match.
+///
+/// A `:name` segment captures exactly one non-empty path segment. A
+/// trailing `*tail` segment captures everything remaining, joined back
+/// together with `/`, and must be the last segment in the pattern.
+pub fn match_route(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let pattern_segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let mut params = HashMap::new();
+    let mut path_iter = path_segments.iter();
+
+    for (i, segment) in pattern_segments.iter().enumerate() {
+        if let Some(name) = segment.strip_prefix('*') {
+            let rest: Vec<&str> = path_iter.by_ref().cloned().collect();
+            if rest.is_empty() {
+                return None;
+            }
+            params.insert(name.to_string(), rest.join("/"));
+            return if i == pattern_segments.len() - 1 {
+                Some(params)
+            } else {
+                None
+            };
+        }
+
+        let path_segment = path_iter.next()?;
+        if let Some(name) = segment.strip_prefix(':') {
+            if path_segment.is_empty() {
+                return None;
+            }
+            params.insert(name.to_string(), path_segment.to_string());
+        } else if *segment != *path_segment {
+            return None;
+        }
+    }
+
+    if path_iter.next().is_some() {
+        None
+    } else {
+        Some(params)
+    }
+}
+
+/// Whether a registered path pattern contains any dynamic segments and
+/// therefore needs `match_route` instead of a plain exact lookup.
+pub fn is_dynamic(pattern: &str) -> bool {
+    pattern
+        .split('/')
+        .any(|segment| segment.starts_with(':') || segment.starts_with('*'))
+}
+
+/// Specificity of a pattern, used to prefer the most concrete match among
+/// several patterns that match the same path. Compared as a tuple where a
+/// larger value always wins: more literal segments beats more `:name`
+/// captures, which beats having a trailing `*tail` at all.
+pub fn specificity(pattern: &str) -> (usize, usize, usize) {
+    let mut literal = 0;
+    let mut named = 0;
+    let mut has_wildcard = 0;
+    for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+        if segment.starts_with('*') {
+            has_wildcard = 1;
+        } else if segment.starts_with(':') {
+            named += 1;
+        } else {
+            literal += 1;
+        }
+    }
+    // No wildcard scores higher than having one.
+    (literal, named, 1 - has_wildcard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_a_literal_path() {
+        let params = match_route("/about", "/about").unwrap();
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn rejects_a_non_matching_literal_path() {
+        assert!(match_route("/about", "/contact").is_none());
+    }
+
+    #[test]
+    fn captures_a_named_segment() {
+        let params = match_route("/user/:id", "/user/42").unwrap();
+        assert_eq!(params.get("id"), Some(&"42".to_string()));
+    }
+
+    #[test]
+    fn rejects_a_missing_named_segment() {
+        assert!(match_route("/user/:id", "/user").is_none());
+    }
+
+    #[test]
+    fn captures_a_wildcard_tail() {
+        let params = match_route("/public/*rest", "/public/css/app.css").unwrap();
+        assert_eq!(params.get("rest"), Some(&"css/app.css".to_string()));
+    }
+
+    #[test]
+    fn rejects_an_empty_wildcard_tail() {
+        assert!(match_route("/public/*rest", "/public").is_none());
+    }
+
+    #[test]
+    fn literal_patterns_are_more_specific_than_named_ones() {
+        assert!(specificity("/user/admin") > specificity("/user/:id"));
+    }
+
+    #[test]
+    fn named_patterns_are_more_specific_than_wildcard_ones() {
+        assert!(specificity("/user/:id") > specificity("/user/*rest"));
+    }
+}
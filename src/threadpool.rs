@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::cache::LruCache;
+use crate::http_parser;
+use crate::http_parser::ParseOutcome;
+use crate::request::Request;
+use crate::response::Response;
+use crate::router;
+
+type MethodMap = HashMap<String, fn(Request, Response) -> Response>;
+type RouteMap = HashMap<String, MethodMap>;
+type MiddlewareList = Vec<(String, fn(Request, Response) -> (Request, Response, bool))>;
+
+/// A fixed pool of worker threads, each handling one connection at a time
+/// off of a shared queue.
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: mpsc::Sender<TcpStream>,
+}
+
+struct Worker {
+    #[allow(dead_code)]
+    id: usize,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    /// Creates a new ThreadPool with `amount_of_threads` worker threads, each
+    /// sharing the given routes and middleware.
+    ///
+    /// #Panics
+    ///
+    /// panics if amount_of_threads is 0
+    pub fn new(
+        amount_of_threads: usize,
+        routes: RouteMap,
+        middleware: MiddlewareList,
+        cache: Arc<Mutex<LruCache>>,
+        request_timeout_secs: u64,
+        keep_alive_secs: u64,
+    ) -> ThreadPool {
+        assert!(amount_of_threads > 0);
+
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let routes = Arc::new(routes);
+        let middleware = Arc::new(middleware);
+
+        let mut workers = Vec::with_capacity(amount_of_threads);
+        for id in 0..amount_of_threads {
+            workers.push(Worker::new(
+                id,
+                Arc::clone(&receiver),
+                Arc::clone(&routes),
+                Arc::clone(&middleware),
+                Arc::clone(&cache),
+                request_timeout_secs,
+                keep_alive_secs,
+            ));
+        }
+
+        ThreadPool { workers, sender }
+    }
+
+    /// Hands a newly accepted connection off to the pool to be handled by a
+    /// worker thread.
+    pub fn execute(&self, stream: TcpStream) {
+        self.sender.send(stream).unwrap();
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+impl Worker {
+    fn new(
+        id: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<TcpStream>>>,
+        routes: Arc<RouteMap>,
+        middleware: Arc<MiddlewareList>,
+        cache: Arc<Mutex<LruCache>>,
+        request_timeout_secs: u64,
+        keep_alive_secs: u64,
+    ) -> Worker {
+        let thread = thread::spawn(move || loop {
+            let stream = match receiver.lock().unwrap().recv() {
+                Ok(stream) => stream,
+                Err(_) => break,
+            };
+            handle_connection(
+                stream,
+                &routes,
+                &middleware,
+                &cache,
+                request_timeout_secs,
+                keep_alive_secs,
+            );
+        });
+
+        Worker {
+            id,
+            thread: Some(thread),
+        }
+    }
+}
+
+/// Handles every request sent on `stream`, keeping the connection open
+/// across requests when `Connection: keep-alive` applies, until the client
+/// closes it, asks for it to be closed, or goes quiet for too long.
+fn handle_connection(
+    mut stream: TcpStream,
+    routes: &RouteMap,
+    middleware: &MiddlewareList,
+    cache: &Arc<Mutex<LruCache>>,
+    request_timeout_secs: u64,
+    keep_alive_secs: u64,
+) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(request_timeout_secs)));
+
+    loop {
+        let request = match http_parser::parse_request(&mut stream, request_timeout_secs) {
+            ParseOutcome::Request(request) => request,
+            ParseOutcome::Closed => return,
+            ParseOutcome::Timeout => {
+                let mut res = Response::new();
+                res.status(408);
+                res.header("connection", "close");
+                http_parser::write_response(&mut stream, &res);
+                return;
+            }
+            ParseOutcome::BodyTooLarge => {
+                let mut res = Response::new();
+                res.status(400);
+                res.header("connection", "close");
+                http_parser::write_response(&mut stream, &res);
+                return;
+            }
+        };
+
+        let keep_alive = should_keep_alive(&request);
+        let res = handle_request(request, routes, middleware, cache, keep_alive);
+        http_parser::write_response(&mut stream, &res);
+
+        if !keep_alive {
+            return;
+        }
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(keep_alive_secs)));
+    }
+}
+
+/// Runs a single request through middleware and the matching route handler.
+fn handle_request(
+    request: Request,
+    routes: &RouteMap,
+    middleware: &MiddlewareList,
+    cache: &Arc<Mutex<LruCache>>,
+    keep_alive: bool,
+) -> Response {
+    let path = normalize_path(&request.url);
+    let mut req = request;
+    req.cache = Some(Arc::clone(cache));
+    let mut res = Response::new();
+
+    for (middleware_path, function) in middleware.iter() {
+        if path.starts_with(middleware_path.as_str()) {
+            let (new_req, new_res, forward) = function(req, res);
+            req = new_req;
+            res = new_res;
+            if !forward {
+                res.header("connection", connection_header(keep_alive));
+                return res;
+            }
+        }
+    }
+
+    match find_route(routes, &path) {
+        Some((methods, params)) => {
+            req.params.extend(params);
+            match methods.get(&req.method) {
+                Some(function) => res = function(req, res),
+                None => {
+                    let allow = allowed_methods(methods);
+                    res.status(if req.method == "OPTIONS" { 204 } else { 405 });
+                    res.header("allow", &allow);
+                }
+            }
+        }
+        None => res.status(404),
+    }
+
+    res.header("connection", connection_header(keep_alive));
+    res
+}
+
+/// Whether the connection a request arrived on should stay open for another
+/// request, based on HTTP version defaults and any explicit `Connection` header.
+fn should_keep_alive(req: &Request) -> bool {
+    match req.header("connection") {
+        Some(value) => !value.eq_ignore_ascii_case("close"),
+        None => req.version == "HTTP/1.1",
+    }
+}
+
+fn connection_header(keep_alive: bool) -> &'static str {
+    if keep_alive {
+        "keep-alive"
+    } else {
+        "close"
+    }
+}
+
+/// Builds the `Allow` header value listing every method registered for a path.
+fn allowed_methods(methods: &MethodMap) -> String {
+    let mut names: Vec<&str> = methods.keys().map(|method| method.as_str()).collect();
+    names.sort();
+    names.join(", ")
+}
+
+/// Finds the route matching `path`, preferring an exact match over a
+/// parameterized one, and the most specific parameterized pattern when
+/// several of those match. Returns the matching method map along with any
+/// `:name`/`*tail` captures.
+fn find_route<'a>(routes: &'a RouteMap, path: &str) -> Option<(&'a MethodMap, HashMap<String, String>)> {
+    if let Some(methods) = routes.get(path) {
+        return Some((methods, HashMap::new()));
+    }
+
+    let mut best: Option<(&str, HashMap<String, String>)> = None;
+    for pattern in routes.keys() {
+        if !router::is_dynamic(pattern) {
+            continue;
+        }
+        if let Some(params) = router::match_route(pattern, path) {
+            // Tie-break on the pattern string itself so that two equally
+            // specific patterns resolve the same way regardless of
+            // `routes.keys()`'s unspecified (and per-process randomized)
+            // iteration order.
+            let is_better = match &best {
+                Some((current, _)) => {
+                    (router::specificity(pattern), pattern.as_str())
+                        > (router::specificity(current), *current)
+                }
+                None => true,
+            };
+            if is_better {
+                best = Some((pattern, params));
+            }
+        }
+    }
+
+    best.map(|(pattern, params)| (routes.get(pattern).unwrap(), params))
+}
+
+/// Strips any query string and trailing slash so that lookups agree with
+/// how `RustHTTPServer::route` normalizes registered paths.
+fn normalize_path(url: &str) -> String {
+    let mut path = url.split('?').next().unwrap_or("/").to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        path.pop();
+    }
+    path
+}